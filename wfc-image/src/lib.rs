@@ -1,12 +1,18 @@
 pub use coord_2d::{Coord, Size};
-use grid_2d::Grid;
+use direction::CardinalDirectionTable;
+use grid_2d::{CoordIter, Grid};
 use image::{DynamicImage, Rgba, RgbaImage};
 use rand::{Rng, SeedableRng};
+use std::hash::Hash;
 use std::num::NonZeroU32;
 use wfc::orientation::OrientationTable;
 pub use wfc::orientation::{self, Orientation};
 use wfc::overlapping::{OverlappingPatterns, Pattern};
 use wfc::retry as wfc_retry;
+pub use wfc::simple_tiled;
+use wfc::simple_tiled::{SimpleTiledPatterns, Tile};
+pub use wfc::transform;
+use wfc::transform::{ReversibleEdgeLabel, SymmetryClass, TransformedTile};
 pub use wfc::wrap;
 pub use wfc::ForbidNothing;
 use wfc::*;
@@ -16,7 +22,7 @@ pub mod retry {
     #[cfg(feature = "parallel")]
     pub use super::wfc_retry::ParNumTimes;
     pub use super::wfc_retry::RetryOwn as Retry;
-    pub use super::wfc_retry::{Forever, NumTimes};
+    pub use super::wfc_retry::{single_connected_component, Forever, NumTimes, RetryUntil};
 
     pub trait ImageRetry: Retry {
         type ImageReturn;
@@ -176,6 +182,19 @@ impl retry::ImageRetry for retry::NumTimes {
     }
 }
 
+impl<P> retry::ImageRetry for retry::RetryUntil<P>
+where
+    P: Fn(&Wave) -> bool,
+{
+    type ImageReturn = DynamicImage;
+    fn image_return(
+        r: Self::Return,
+        image_patterns: &ImagePatterns,
+    ) -> Self::ImageReturn {
+        image_patterns.image_from_wave(&r)
+    }
+}
+
 #[cfg(feature = "parallel")]
 impl retry::ImageRetry for retry::ParNumTimes {
     type ImageReturn = Result<DynamicImage, PropagateError>;
@@ -190,6 +209,210 @@ impl retry::ImageRetry for retry::ParNumTimes {
     }
 }
 
+/// A tile for use with `SimpleTiledImagePatterns`: a `tile_size` x `tile_size` image plus one
+/// edge code per `CardinalDirection` and an optional weight, mirroring `Tile`.
+pub struct ImageTile<E> {
+    pub image: DynamicImage,
+    pub edges: CardinalDirectionTable<E>,
+    pub weight: Option<NonZeroU32>,
+}
+
+impl<E> ImageTile<E> {
+    pub fn new(
+        image: DynamicImage,
+        edges: CardinalDirectionTable<E>,
+        weight: Option<NonZeroU32>,
+    ) -> Self {
+        Self {
+            image,
+            edges,
+            weight,
+        }
+    }
+}
+
+/// Like `ImageTile`, but authored once alongside a `SymmetryClass` and expanded into every
+/// distinct oriented copy by `SimpleTiledImagePatterns::new_with_symmetry`.
+pub struct SymmetricImageTile<E> {
+    pub image: DynamicImage,
+    pub edges: CardinalDirectionTable<E>,
+    pub weight: Option<NonZeroU32>,
+    pub symmetry: SymmetryClass,
+}
+
+impl<E> SymmetricImageTile<E> {
+    pub fn new(
+        image: DynamicImage,
+        edges: CardinalDirectionTable<E>,
+        weight: Option<NonZeroU32>,
+        symmetry: SymmetryClass,
+    ) -> Self {
+        Self {
+            image,
+            edges,
+            weight,
+            symmetry,
+        }
+    }
+}
+
+fn image_to_tile_grid(image: &DynamicImage, tile_size: Size) -> Grid<Rgba<u8>> {
+    let rgba_image = image.to_rgba8();
+    assert!(Size::new(rgba_image.width(), rgba_image.height()) == tile_size);
+    Grid::new_fn(tile_size, |Coord { x, y }| {
+        *rgba_image.get_pixel(x as u32, y as u32)
+    })
+}
+
+/// Samples `grid` (assumed square) as though it had been rotated/reflected by `orientation`,
+/// permuting pixel coordinates the same way `transform::source_direction` permutes edges.
+fn apply_orientation(grid: &Grid<Rgba<u8>>, orientation: Orientation) -> Grid<Rgba<u8>> {
+    let size = grid.size();
+    assert!(size.width() == size.height(), "transformed tiles must be square");
+    let n = size.width() as i32;
+    Grid::new_fn(size, |Coord { x, y }| {
+        use Orientation::*;
+        let source = match orientation {
+            Original => Coord::new(x, y),
+            Clockwise90 => Coord::new(y, n - 1 - x),
+            Clockwise180 => Coord::new(n - 1 - x, n - 1 - y),
+            Clockwise270 => Coord::new(n - 1 - y, x),
+            DiagonalFlip => Coord::new(n - 1 - x, y),
+            DiagonalFlippedClockwise90 => Coord::new(n - 1 - y, n - 1 - x),
+            DiagonalFlippedClockwise180 => Coord::new(x, n - 1 - y),
+            DiagonalFlippedClockwise270 => Coord::new(y, x),
+        };
+        *grid.get_checked(source)
+    })
+}
+
+/// The simple-tiled analogue of `ImagePatterns`: adjacency comes from matching edge codes on a
+/// fixed set of `tile_size` x `tile_size` tile images rather than from an overlapping exemplar.
+pub struct SimpleTiledImagePatterns<E: Eq + Clone + Hash> {
+    pub simple_tiled_patterns: SimpleTiledPatterns<Grid<Rgba<u8>>, E>,
+    tile_size: Size,
+    empty_colour: Rgba<u8>,
+}
+
+impl<E: Eq + Clone + Hash> SimpleTiledImagePatterns<E> {
+    pub fn new(tile_size: Size, tiles: Vec<ImageTile<E>>) -> Self {
+        let tiles = tiles
+            .into_iter()
+            .map(|tile| {
+                let grid = image_to_tile_grid(&tile.image, tile_size);
+                Tile::new(grid, tile.edges, tile.weight)
+            })
+            .collect::<Vec<_>>();
+        Self {
+            simple_tiled_patterns: SimpleTiledPatterns::new(tiles),
+            tile_size,
+            empty_colour: Rgba([0, 0, 0, 0]),
+        }
+    }
+
+    /// Like `new`, but each input tile is authored once and expanded into every distinct
+    /// rotation/reflection implied by its `SymmetryClass`, with edge codes permuted to match.
+    pub fn new_with_symmetry(tile_size: Size, tiles: Vec<SymmetricImageTile<E>>) -> Self
+    where
+        E: Default + ReversibleEdgeLabel,
+    {
+        let tiles = tiles
+            .into_iter()
+            .flat_map(|tile| {
+                let grid = image_to_tile_grid(&tile.image, tile_size);
+                transform::expand_tile(
+                    TransformedTile {
+                        value: grid,
+                        edges: tile.edges,
+                        weight: tile.weight,
+                        symmetry: tile.symmetry,
+                    },
+                    |value, orientation| apply_orientation(value, orientation),
+                )
+            })
+            .collect::<Vec<_>>();
+        Self {
+            simple_tiled_patterns: SimpleTiledPatterns::new(tiles),
+            tile_size,
+            empty_colour: Rgba([0, 0, 0, 0]),
+        }
+    }
+
+    pub fn set_empty_colour(&mut self, empty_colour: Rgba<u8>) {
+        self.empty_colour = empty_colour;
+    }
+
+    pub fn global_stats(&self) -> GlobalStats {
+        self.simple_tiled_patterns.global_stats()
+    }
+
+    pub fn image_from_wave(&self, wave: &Wave) -> DynamicImage {
+        let wave_size = wave.grid().size();
+        let output_size = Size::new(
+            wave_size.width() * self.tile_size.width(),
+            wave_size.height() * self.tile_size.height(),
+        );
+        let mut rgba_image = RgbaImage::new(output_size.width(), output_size.height());
+        wave.grid().enumerate().for_each(|(coord, cell)| {
+            let tile_origin = Coord::new(
+                coord.x * self.tile_size.width() as i32,
+                coord.y * self.tile_size.height() as i32,
+            );
+            CoordIter::new(self.tile_size).for_each(|tile_coord| {
+                let colour = match cell.chosen_pattern_id() {
+                    Ok(pattern_id) => *self
+                        .simple_tiled_patterns
+                        .tile_value(pattern_id)
+                        .get_checked(tile_coord),
+                    Err(_) => self.empty_colour,
+                };
+                let out = tile_origin + tile_coord;
+                rgba_image.put_pixel(out.x as u32, out.y as u32, colour);
+            });
+        });
+        DynamicImage::ImageRgba8(rgba_image)
+    }
+
+    pub fn weighted_average_colour<'a>(&self, cell: &'a WaveCellRef<'a>) -> Grid<Rgba<u8>> {
+        use wfc::EnumerateCompatiblePatternWeights::*;
+        match cell.enumerate_compatible_pattern_weights() {
+            MultipleCompatiblePatternsWithoutWeights | NoCompatiblePattern => {
+                Grid::new_clone(self.tile_size, self.empty_colour)
+            }
+            SingleCompatiblePatternWithoutWeight(pattern_id) => {
+                self.simple_tiled_patterns.tile_value(pattern_id).clone()
+            }
+            CompatiblePatternsWithWeights(iter) => {
+                let weighted = iter.collect::<Vec<_>>();
+                let total_weight = cell.sum_compatible_pattern_weight();
+                Grid::new_fn(self.tile_size, |tile_coord| {
+                    let (r, g, b, a) = weighted.iter().fold(
+                        (0u32, 0u32, 0u32, 0u32),
+                        |(acc_r, acc_g, acc_b, acc_a), &(pattern_id, weight)| {
+                            let &Rgba([r, g, b, a]) = self
+                                .simple_tiled_patterns
+                                .tile_value(pattern_id)
+                                .get_checked(tile_coord);
+                            (
+                                acc_r + r as u32 * weight,
+                                acc_g + g as u32 * weight,
+                                acc_b + b as u32 * weight,
+                                acc_a + a as u32 * weight,
+                            )
+                        },
+                    );
+                    Rgba([
+                        (r / total_weight) as u8,
+                        (g / total_weight) as u8,
+                        (b / total_weight) as u8,
+                        (a / total_weight) as u8,
+                    ])
+                })
+            }
+        }
+    }
+}
+
 pub fn generate_image_with_rng<W, F, IR, R>(
     image: &DynamicImage,
     pattern_size: NonZeroU32,
@@ -238,3 +461,66 @@ where
         &mut rand::rngs::StdRng::from_entropy(),
     )
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn grid_from_rows(rows: [[Rgba<u8>; 3]; 3]) -> Grid<Rgba<u8>> {
+        Grid::new_fn(Size::new(3, 3), |Coord { x, y }| {
+            rows[y as usize][x as usize]
+        })
+    }
+
+    // `apply_orientation` against hand-derived dihedral transforms of a 3x3 grid with a
+    // distinct colour per cell (labelled a..i in row-major order): each expected layout is
+    // the grid as it would physically look after the corresponding rotation/reflection,
+    // matching the edge mapping `transform::source_direction` documents (e.g. a 90 degree
+    // clockwise rotation brings the original west edge to the north side).
+    #[test]
+    fn apply_orientation_matches_dihedral_geometry() {
+        let colours: Vec<Rgba<u8>> = (0..9).map(|i| Rgba([i as u8, 0, 0, 255])).collect();
+        let (a, b, c, d, e, f, g, h, i) = (
+            colours[0], colours[1], colours[2], colours[3], colours[4], colours[5], colours[6],
+            colours[7], colours[8],
+        );
+        let source = grid_from_rows([[a, b, c], [d, e, f], [g, h, i]]);
+
+        let cases = [
+            ("Original", Orientation::Original, [[a, b, c], [d, e, f], [g, h, i]]),
+            ("Clockwise90", Orientation::Clockwise90, [[g, d, a], [h, e, b], [i, f, c]]),
+            ("Clockwise180", Orientation::Clockwise180, [[i, h, g], [f, e, d], [c, b, a]]),
+            ("Clockwise270", Orientation::Clockwise270, [[c, f, i], [b, e, h], [a, d, g]]),
+            ("DiagonalFlip", Orientation::DiagonalFlip, [[c, b, a], [f, e, d], [i, h, g]]),
+            (
+                "DiagonalFlippedClockwise90",
+                Orientation::DiagonalFlippedClockwise90,
+                [[i, f, c], [h, e, b], [g, d, a]],
+            ),
+            (
+                "DiagonalFlippedClockwise180",
+                Orientation::DiagonalFlippedClockwise180,
+                [[g, h, i], [d, e, f], [a, b, c]],
+            ),
+            (
+                "DiagonalFlippedClockwise270",
+                Orientation::DiagonalFlippedClockwise270,
+                [[a, d, g], [b, e, h], [c, f, i]],
+            ),
+        ];
+
+        for (name, orientation, expected_rows) in cases {
+            let expected = grid_from_rows(expected_rows);
+            let actual = apply_orientation(&source, orientation);
+            for coord in CoordIter::new(Size::new(3, 3)) {
+                assert_eq!(
+                    actual.get_checked(coord),
+                    expected.get_checked(coord),
+                    "orientation {} mismatch at {:?}",
+                    name,
+                    coord
+                );
+            }
+        }
+    }
+}
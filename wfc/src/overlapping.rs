@@ -10,6 +10,23 @@ use hashbrown::HashMap;
 use std::hash::Hash;
 use std::num::NonZeroU32;
 
+// For a pair of patterns placed with `b` towards `b_offset_direction` of `a`, this is the
+// coordinate within each pattern at which the shared overlap region begins: both offsets
+// locate the side of their pattern that touches the other (`a`'s side facing `b`, and `b`'s
+// side facing `a`).
+fn overlap_offsets(b_offset_direction: CardinalDirection) -> (Coord, Coord) {
+    match b_offset_direction {
+        CardinalDirection::North => (Coord::new(0, 0), Coord::new(0, 1)),
+        CardinalDirection::South => (Coord::new(0, 1), Coord::new(0, 0)),
+        CardinalDirection::East => (Coord::new(1, 0), Coord::new(0, 0)),
+        CardinalDirection::West => (Coord::new(0, 0), Coord::new(1, 0)),
+    }
+}
+
+// Brute-force reference implementation of pattern compatibility, kept only as a test oracle
+// for `compatible_patterns`' hashed-edge index: compares the overlap region cell-by-cell
+// instead of via a precomputed key.
+#[cfg(test)]
 fn are_patterns_compatible<T: PartialEq>(
     a: &TiledGridSlice<T>,
     b: &TiledGridSlice<T>,
@@ -23,18 +40,25 @@ fn are_patterns_compatible<T: PartialEq>(
     }
     let axis = b_offset_direction.axis();
     let compare_size = size.with_axis(axis, |d| d - 1);
-    let (a_offset, b_offset) = match b_offset_direction {
-        CardinalDirection::North => (Coord::new(0, 0), Coord::new(0, 1)),
-        CardinalDirection::South => (Coord::new(0, 1), Coord::new(0, 0)),
-        CardinalDirection::East => (Coord::new(1, 0), Coord::new(0, 0)),
-        CardinalDirection::West => (Coord::new(0, 0), Coord::new(1, 0)),
-    };
+    let (a_offset, b_offset) = overlap_offsets(b_offset_direction);
     let coords = || CoordIter::new(compare_size);
     let a_iter = coords().map(|c| a.get_checked(c + a_offset));
     let b_iter = coords().map(|c| b.get_checked(c + b_offset));
     a_iter.zip(b_iter).all(|(a, b)| a == b)
 }
 
+// The cells of `slice` that lie in the overlap region on its `direction` side, in a fixed
+// `CoordIter` order. Two patterns are compatible with one another placed towards `direction`
+// iff their edge keys on opposite sides are equal: `edge_key(p, d) == edge_key(q, d.opposite())`.
+fn edge_key<T: Clone>(slice: &TiledGridSlice<T>, direction: CardinalDirection) -> Vec<T> {
+    let size = slice.size();
+    let compare_size = size.with_axis(direction.axis(), |d| d - 1);
+    let (offset, _) = overlap_offsets(direction);
+    CoordIter::new(compare_size)
+        .map(|c| slice.get_checked(c + offset).clone())
+        .collect()
+}
+
 #[derive(Debug)]
 pub struct Pattern {
     id: PatternId,
@@ -67,11 +91,17 @@ impl Pattern {
     }
 }
 
+// Maps, for each `CardinalDirection`, the edge key of that side of a pattern to the ids of
+// all patterns sharing that edge key, so `compatible_patterns` becomes a single lookup instead
+// of a scan over every other pattern.
+type CompatibilityIndex<T> = CardinalDirectionTable<HashMap<Vec<T>, Vec<PatternId>>>;
+
 pub struct OverlappingPatterns<T: Eq + Clone + Hash> {
     pattern_table: PatternTable<Pattern>,
     pattern_size: Size,
     grid: Grid<T>,
     id_grid: Grid<OrientationTable<PatternId>>,
+    compatibility_index: CompatibilityIndex<T>,
 }
 
 impl<T: Eq + Clone + Hash> OverlappingPatterns<T> {
@@ -110,11 +140,27 @@ impl<T: Eq + Clone + Hash> OverlappingPatterns<T> {
             patterns.sort_by_key(|pattern| pattern.id);
             PatternTable::from_vec(patterns)
         };
+        let compatibility_index = {
+            let mut index = CompatibilityIndex::<T>::default();
+            if pattern_size.x() != 1 {
+                for pattern in pattern_table.iter() {
+                    let slice = pattern.tiled_grid_slice(&grid, pattern_size);
+                    for direction in CardinalDirections {
+                        index[direction]
+                            .entry(edge_key(&slice, direction))
+                            .or_insert_with(Vec::new)
+                            .push(pattern.id);
+                    }
+                }
+            }
+            index
+        };
         Self {
             pattern_table,
             pattern_size,
             grid,
             id_grid,
+            compatibility_index,
         }
     }
     pub fn new_all_orientations(grid: Grid<T>, pattern_size: NonZeroU32) -> Self {
@@ -155,19 +201,18 @@ impl<T: Eq + Clone + Hash> OverlappingPatterns<T> {
         pattern: &'b Pattern,
         direction: CardinalDirection,
     ) -> impl 'b + Iterator<Item = PatternId> {
-        let tiled_grid_slice = pattern.tiled_grid_slice(&self.grid, self.pattern_size);
-        self.pattern_table
-            .enumerate()
-            .filter(move |(_id, other)| {
-                let other_tiled_grid_slice =
-                    other.tiled_grid_slice(&self.grid, self.pattern_size);
-                are_patterns_compatible(
-                    &tiled_grid_slice,
-                    &other_tiled_grid_slice,
-                    direction,
-                )
-            })
-            .map(|(id, _other)| id)
+        let ids = if self.pattern_size.x() == 1 {
+            // patterns don't overlap, so everything is compatible
+            self.pattern_table.enumerate().map(|(id, _)| id).collect()
+        } else {
+            let tiled_grid_slice = pattern.tiled_grid_slice(&self.grid, self.pattern_size);
+            let key = edge_key(&tiled_grid_slice, direction);
+            self.compatibility_index[direction.opposite()]
+                .get(&key)
+                .cloned()
+                .unwrap_or_default()
+        };
+        ids.into_iter()
     }
     pub fn pattern_descriptions(&self) -> PatternTable<PatternDescription> {
         self.pattern_table
@@ -195,42 +240,129 @@ mod test {
     use coord_2d::{Coord, Size};
     use direction::CardinalDirection;
     use grid_2d::Grid;
-    use orientation::Orientation;
 
-    fn pattern_with_coord(coord: Coord) -> Pattern {
-        let mut pattern = Pattern::new(0, Orientation::Original);
-        pattern.coords.push(coord);
-        pattern
-    }
-
-    #[test]
-    fn compatibile_patterns() {
+    fn small_grid() -> Grid<u8> {
         let r = 0;
         let b = 1;
         let array = [[r, b, b], [b, r, b]];
-        let grid = Grid::new_fn(Size::new(3, 2), |coord| {
+        Grid::new_fn(Size::new(3, 2), |coord| {
             array[coord.y as usize][coord.x as usize]
-        });
-        let pattern_size = Size::new(2, 2);
-        assert!(are_patterns_compatible(
-            &pattern_with_coord(Coord::new(0, 0)).tiled_grid_slice(&grid, pattern_size),
-            &pattern_with_coord(Coord::new(1, 0)).tiled_grid_slice(&grid, pattern_size),
-            CardinalDirection::East,
-        ));
-        assert!(are_patterns_compatible(
-            &pattern_with_coord(Coord::new(0, 0)).tiled_grid_slice(&grid, pattern_size),
-            &pattern_with_coord(Coord::new(1, 0)).tiled_grid_slice(&grid, pattern_size),
-            CardinalDirection::North,
-        ));
-        assert!(!are_patterns_compatible(
-            &pattern_with_coord(Coord::new(0, 0)).tiled_grid_slice(&grid, pattern_size),
-            &pattern_with_coord(Coord::new(1, 0)).tiled_grid_slice(&grid, pattern_size),
-            CardinalDirection::South,
-        ));
-        assert!(!are_patterns_compatible(
-            &pattern_with_coord(Coord::new(0, 0)).tiled_grid_slice(&grid, pattern_size),
-            &pattern_with_coord(Coord::new(1, 0)).tiled_grid_slice(&grid, pattern_size),
-            CardinalDirection::West,
-        ));
+        })
+    }
+
+    #[test]
+    fn compatible_patterns_edge_index() {
+        let overlapping =
+            OverlappingPatterns::new_original_orientation(small_grid(), NonZeroU32::new(2).unwrap());
+        let id_grid = overlapping.id_grid_original_orientation();
+        let a_id = *id_grid.get_checked(Coord::new(0, 0));
+        let b_id = *id_grid.get_checked(Coord::new(1, 0));
+        let a_pattern = overlapping.pattern(a_id);
+
+        let compatible = |direction| {
+            overlapping
+                .compatible_patterns(a_pattern, direction)
+                .collect::<Vec<_>>()
+        };
+        assert!(compatible(CardinalDirection::East).contains(&b_id));
+        assert!(compatible(CardinalDirection::North).contains(&b_id));
+        assert!(!compatible(CardinalDirection::South).contains(&b_id));
+        assert!(!compatible(CardinalDirection::West).contains(&b_id));
+    }
+
+    #[test]
+    fn pattern_descriptions_match_edge_index() {
+        let overlapping =
+            OverlappingPatterns::new_original_orientation(small_grid(), NonZeroU32::new(2).unwrap());
+        let pattern_descriptions = overlapping.pattern_descriptions();
+        for pattern in overlapping.pattern_table.iter() {
+            let description = &pattern_descriptions[pattern.id];
+            for direction in CardinalDirections {
+                // Unsorted: `pattern_descriptions` must produce byte-identical neighbour
+                // lists, in the same order `compatible_patterns` yields them, not just the
+                // same set.
+                let expected = overlapping
+                    .compatible_patterns(pattern, direction)
+                    .collect::<Vec<_>>();
+                let actual = description.allowed_neighbours[direction].clone();
+                assert_eq!(expected, actual);
+            }
+        }
+    }
+
+    #[test]
+    fn compatible_patterns_matches_brute_force_oracle() {
+        let grids = vec![
+            small_grid(),
+            {
+                let array = [[0u8, 1, 2, 1], [1, 0, 1, 2], [2, 1, 0, 1]];
+                Grid::new_fn(Size::new(4, 3), |coord| {
+                    array[coord.y as usize][coord.x as usize]
+                })
+            },
+            {
+                let array = [[0u8, 0, 0], [0, 1, 0], [0, 0, 0]];
+                Grid::new_fn(Size::new(3, 3), |coord| {
+                    array[coord.y as usize][coord.x as usize]
+                })
+            },
+        ];
+        for grid in grids {
+            for pattern_size in [1, 2, 3] {
+                if pattern_size > grid.size().x() as usize
+                    || pattern_size > grid.size().y() as usize
+                {
+                    continue;
+                }
+                let overlapping = OverlappingPatterns::new_original_orientation(
+                    grid.clone(),
+                    NonZeroU32::new(pattern_size as u32).unwrap(),
+                );
+                let pattern_size_2d = Size::new(pattern_size as u32, pattern_size as u32);
+                for a in overlapping.pattern_table.iter() {
+                    let a_slice = a.tiled_grid_slice(&grid, pattern_size_2d);
+                    for direction in CardinalDirections {
+                        // Unsorted: both sides walk `pattern_table` in ascending id order, so
+                        // this pins the exact order `compatible_patterns` must return, not
+                        // just which ids are compatible.
+                        let expected = overlapping
+                            .pattern_table
+                            .iter()
+                            .filter(|b| {
+                                let b_slice = b.tiled_grid_slice(&grid, pattern_size_2d);
+                                are_patterns_compatible(&a_slice, &b_slice, direction)
+                            })
+                            .map(|b| b.id)
+                            .collect::<Vec<_>>();
+                        let actual = overlapping
+                            .compatible_patterns(a, direction)
+                            .collect::<Vec<_>>();
+                        assert_eq!(expected, actual);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn single_column_pattern_size_everything_compatible() {
+        let overlapping =
+            OverlappingPatterns::new_original_orientation(small_grid(), NonZeroU32::new(1).unwrap());
+        let all_ids = overlapping
+            .pattern_table
+            .iter()
+            .map(|pattern| pattern.id)
+            .collect::<Vec<_>>();
+        for pattern in overlapping.pattern_table.iter() {
+            for direction in CardinalDirections {
+                let mut compatible = overlapping
+                    .compatible_patterns(pattern, direction)
+                    .collect::<Vec<_>>();
+                let mut expected = all_ids.clone();
+                compatible.sort();
+                expected.sort();
+                assert_eq!(compatible, expected);
+            }
+        }
     }
 }
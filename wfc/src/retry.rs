@@ -0,0 +1,200 @@
+use crate::wfc::{PatternId, PropagateError, Wave};
+use coord_2d::{Coord, Size};
+use direction::CardinalDirections;
+use grid_2d::{CoordIter, Grid};
+
+pub trait RetryOwn {
+    type Return;
+    fn retry<F>(&mut self, attempt: F) -> Self::Return
+    where
+        F: Fn() -> Result<Wave, PropagateError> + Send + Sync;
+}
+
+/// Retries forever until a collapse succeeds, ignoring `PropagateError`s.
+pub struct Forever;
+
+impl RetryOwn for Forever {
+    type Return = Wave;
+    fn retry<F>(&mut self, attempt: F) -> Self::Return
+    where
+        F: Fn() -> Result<Wave, PropagateError> + Send + Sync,
+    {
+        loop {
+            if let Ok(wave) = attempt() {
+                return wave;
+            }
+        }
+    }
+}
+
+/// Retries up to `num_times` times, returning the first success or the final error.
+pub struct NumTimes(pub usize);
+
+impl RetryOwn for NumTimes {
+    type Return = Result<Wave, PropagateError>;
+    fn retry<F>(&mut self, attempt: F) -> Self::Return
+    where
+        F: Fn() -> Result<Wave, PropagateError> + Send + Sync,
+    {
+        let mut last_err = None;
+        for _ in 0..self.0 {
+            match attempt() {
+                Ok(wave) => return Ok(wave),
+                Err(err) => last_err = Some(err),
+            }
+        }
+        Err(last_err.expect("num_times must be greater than zero"))
+    }
+}
+
+#[cfg(feature = "parallel")]
+pub struct ParNumTimes(pub usize);
+
+#[cfg(feature = "parallel")]
+impl RetryOwn for ParNumTimes {
+    type Return = Result<Wave, PropagateError>;
+    fn retry<F>(&mut self, attempt: F) -> Self::Return
+    where
+        F: Fn() -> Result<Wave, PropagateError> + Send + Sync,
+    {
+        use rayon::iter::{IntoParallelIterator, ParallelIterator};
+        (0..self.0)
+            .into_par_iter()
+            .map(|_| attempt())
+            .find_any(|result| result.is_ok())
+            .unwrap_or_else(|| attempt())
+    }
+}
+
+/// Retries forever like `Forever`, but additionally rejects any completed `Wave` that fails a
+/// user-supplied predicate over the finished grid, restarting the collapse until one is found
+/// that satisfies it. This lets callers enforce semantic properties (e.g. connectivity) that
+/// `PropagateError` alone can't express.
+pub struct RetryUntil<P> {
+    predicate: P,
+}
+
+impl<P> RetryUntil<P>
+where
+    P: Fn(&Wave) -> bool,
+{
+    pub fn new(predicate: P) -> Self {
+        Self { predicate }
+    }
+}
+
+impl<P> RetryOwn for RetryUntil<P>
+where
+    P: Fn(&Wave) -> bool,
+{
+    type Return = Wave;
+    fn retry<F>(&mut self, attempt: F) -> Self::Return
+    where
+        F: Fn() -> Result<Wave, PropagateError> + Send + Sync,
+    {
+        loop {
+            if let Ok(wave) = attempt() {
+                if (self.predicate)(&wave) {
+                    return wave;
+                }
+            }
+        }
+    }
+}
+
+/// Returns true iff every `true` cell of `passable` belongs to a single 4-connected component
+/// (vacuously true if there are no passable cells at all). Kept free of `Wave` so the flood-fill
+/// logic can be unit tested without a full solver setup.
+fn single_connected_component_grid(passable: &Grid<bool>) -> bool {
+    let size = passable.size();
+    let in_bounds = |coord: Coord| {
+        coord.x >= 0 && coord.y >= 0 && coord.x < size.width() as i32 && coord.y < size.height() as i32
+    };
+    let mut total_passable = 0;
+    let mut start = None;
+    for coord in CoordIter::new(size) {
+        if *passable.get_checked(coord) {
+            total_passable += 1;
+            if start.is_none() {
+                start = Some(coord);
+            }
+        }
+    }
+    let start = match start {
+        Some(coord) => coord,
+        None => return true,
+    };
+    let mut visited = Grid::new_clone(size, false);
+    let mut stack = vec![start];
+    *visited.get_checked_mut(start) = true;
+    let mut component_size = 0;
+    while let Some(coord) = stack.pop() {
+        component_size += 1;
+        for direction in CardinalDirections {
+            let neighbour = coord + direction.coord();
+            if in_bounds(neighbour)
+                && !*visited.get_checked(neighbour)
+                && *passable.get_checked(neighbour)
+            {
+                *visited.get_checked_mut(neighbour) = true;
+                stack.push(neighbour);
+            }
+        }
+    }
+    component_size == total_passable
+}
+
+/// Builds a `RetryUntil` predicate that treats every cell whose collapsed pattern's value (as
+/// given by `pattern_value`, e.g. `OverlappingPatterns::pattern_top_left_value`) is in
+/// `passable` as traversable, and accepts a `Wave` only if every such cell belongs to a single
+/// 4-connected component.
+pub fn single_connected_component<T, F>(
+    passable: Vec<T>,
+    pattern_value: F,
+) -> impl Fn(&Wave) -> bool
+where
+    T: PartialEq,
+    F: Fn(PatternId) -> T,
+{
+    move |wave: &Wave| {
+        let grid = wave.grid();
+        let passable_grid = Grid::new_fn(grid.size(), |coord| {
+            grid.get_checked(coord)
+                .chosen_pattern_id()
+                .map(|id| passable.contains(&pattern_value(id)))
+                .unwrap_or(false)
+        });
+        single_connected_component_grid(&passable_grid)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn grid_from_rows(rows: &[&[bool]]) -> Grid<bool> {
+        let height = rows.len() as u32;
+        let width = rows[0].len() as u32;
+        Grid::new_fn(Size::new(width, height), |coord| {
+            rows[coord.y as usize][coord.x as usize]
+        })
+    }
+
+    #[test]
+    fn no_passable_cells_is_vacuously_connected() {
+        let grid = grid_from_rows(&[&[false, false], &[false, false]]);
+        assert!(single_connected_component_grid(&grid));
+    }
+
+    #[test]
+    fn single_connected_region_is_connected() {
+        let grid = grid_from_rows(&[&[true, true, false], &[false, true, false]]);
+        assert!(single_connected_component_grid(&grid));
+    }
+
+    #[test]
+    fn two_disconnected_regions_are_rejected() {
+        let grid = grid_from_rows(&[&[true, false, true], &[false, false, false]]);
+        assert!(!single_connected_component_grid(&grid));
+    }
+}
@@ -0,0 +1,159 @@
+use crate::wfc::{GlobalStats, PatternDescription, PatternId, PatternTable};
+use direction::{CardinalDirection, CardinalDirectionTable, CardinalDirections};
+use hashbrown::HashMap;
+use std::hash::Hash;
+use std::num::NonZeroU32;
+
+/// A single tile in a simple-tiled model: an opaque payload (e.g. an image) together with one
+/// edge code per `CardinalDirection`. Two tiles are compatible neighbours in direction `d` iff
+/// their edge codes on the shared edge are equal, rather than by comparing overlapping pixels
+/// as `OverlappingPatterns` does.
+#[derive(Debug, Clone)]
+pub struct Tile<T, E> {
+    value: T,
+    edges: CardinalDirectionTable<E>,
+    weight: Option<NonZeroU32>,
+}
+
+impl<T, E> Tile<T, E> {
+    pub fn new(value: T, edges: CardinalDirectionTable<E>, weight: Option<NonZeroU32>) -> Self {
+        Self {
+            value,
+            edges,
+            weight,
+        }
+    }
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+    pub(crate) fn edges(&self) -> &CardinalDirectionTable<E> {
+        &self.edges
+    }
+}
+
+pub struct SimpleTiledPatterns<T, E: Eq + Clone + Hash> {
+    tiles: PatternTable<Tile<T, E>>,
+    compatibility_index: CardinalDirectionTable<HashMap<E, Vec<PatternId>>>,
+}
+
+impl<T, E: Eq + Clone + Hash> SimpleTiledPatterns<T, E> {
+    pub fn new(tiles: Vec<Tile<T, E>>) -> Self {
+        let mut compatibility_index: CardinalDirectionTable<HashMap<E, Vec<PatternId>>> =
+            CardinalDirectionTable::default();
+        let mut next_id = 0;
+        let tiles = tiles
+            .into_iter()
+            .map(|tile| {
+                let id = next_id;
+                next_id += 1;
+                for direction in CardinalDirections {
+                    compatibility_index[direction]
+                        .entry(tile.edges[direction].clone())
+                        .or_insert_with(Vec::new)
+                        .push(id);
+                }
+                tile
+            })
+            .collect::<Vec<_>>();
+        Self {
+            tiles: PatternTable::from_vec(tiles),
+            compatibility_index,
+        }
+    }
+    pub fn tile(&self, pattern_id: PatternId) -> &Tile<T, E> {
+        &self.tiles[pattern_id]
+    }
+    pub fn tile_value(&self, pattern_id: PatternId) -> &T {
+        self.tile(pattern_id).value()
+    }
+    pub fn compatible_patterns<'b>(
+        &'b self,
+        tile: &'b Tile<T, E>,
+        direction: CardinalDirection,
+    ) -> impl 'b + Iterator<Item = PatternId> {
+        self.compatibility_index[direction.opposite()]
+            .get(&tile.edges[direction])
+            .cloned()
+            .unwrap_or_default()
+            .into_iter()
+    }
+    pub fn pattern_descriptions(&self) -> PatternTable<PatternDescription> {
+        self.tiles
+            .iter()
+            .map(|tile| {
+                let mut allowed_neighbours = CardinalDirectionTable::default();
+                for direction in CardinalDirections {
+                    allowed_neighbours[direction] = self
+                        .compatible_patterns(tile, direction)
+                        .collect::<Vec<_>>();
+                }
+                PatternDescription::new(tile.weight, allowed_neighbours)
+            })
+            .collect::<PatternTable<_>>()
+    }
+    pub fn global_stats(&self) -> GlobalStats {
+        GlobalStats::new(self.pattern_descriptions())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn edges(
+        north: &'static str,
+        south: &'static str,
+        east: &'static str,
+        west: &'static str,
+    ) -> CardinalDirectionTable<&'static str> {
+        let mut table = CardinalDirectionTable::default();
+        table[CardinalDirection::North] = north;
+        table[CardinalDirection::South] = south;
+        table[CardinalDirection::East] = east;
+        table[CardinalDirection::West] = west;
+        table
+    }
+
+    #[test]
+    fn compatible_patterns_match_by_edge_code() {
+        // `a` only matches `b` to its north and `c` to its south; no other edge codes are shared
+        // across opposite sides, so east/west neighbours of `a` are empty.
+        let a = Tile::new("a", edges("a0", "a1", "a2", "a3"), None);
+        let b = Tile::new("b", edges("b0", "a0", "b2", "b3"), None);
+        let c = Tile::new("c", edges("a1", "c1", "c2", "c3"), None);
+        let simple_tiled = SimpleTiledPatterns::new(vec![a, b, c]);
+        let a_id = 0;
+        let b_id = 1;
+        let c_id = 2;
+        let a_tile = simple_tiled.tile(a_id);
+
+        assert_eq!(
+            simple_tiled
+                .compatible_patterns(a_tile, CardinalDirection::North)
+                .collect::<Vec<_>>(),
+            vec![b_id]
+        );
+        assert_eq!(
+            simple_tiled
+                .compatible_patterns(a_tile, CardinalDirection::South)
+                .collect::<Vec<_>>(),
+            vec![c_id]
+        );
+        assert!(simple_tiled
+            .compatible_patterns(a_tile, CardinalDirection::East)
+            .collect::<Vec<_>>()
+            .is_empty());
+        assert!(simple_tiled
+            .compatible_patterns(a_tile, CardinalDirection::West)
+            .collect::<Vec<_>>()
+            .is_empty());
+
+        let b_tile = simple_tiled.tile(b_id);
+        assert_eq!(
+            simple_tiled
+                .compatible_patterns(b_tile, CardinalDirection::South)
+                .collect::<Vec<_>>(),
+            vec![a_id]
+        );
+    }
+}
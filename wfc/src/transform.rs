@@ -0,0 +1,212 @@
+use crate::orientation::Orientation;
+use crate::simple_tiled::Tile;
+use direction::{CardinalDirection, CardinalDirectionTable, CardinalDirections};
+use std::num::NonZeroU32;
+
+/// The classic WFC tile symmetry classes, named after a representative glyph of each shape.
+/// Each class fixes how many of the 8 members of the dihedral group of order 8 are distinct
+/// for a tile with that shape, so only that many oriented copies need to be generated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymmetryClass {
+    /// Symmetric under every rotation and reflection (e.g. a blank tile, a plus sign).
+    X,
+    /// Symmetric under a horizontal flip, distinct under rotation (e.g. the letter T).
+    T,
+    /// Symmetric under a 180 degree rotation, distinct under a 90 degree rotation (e.g. I).
+    I,
+    /// Symmetric under no rotation, but under a reflection across one diagonal: its mirror
+    /// image is the tile rotated 270 degrees, so only the 4 rotations are distinct (e.g. the
+    /// letter L).
+    L,
+    /// Symmetric under a reflection across its diagonal and under a 180 degree rotation,
+    /// distinct under a 90 degree rotation (e.g. a backslash).
+    Diagonal,
+    /// No symmetry: all 8 transforms produce a distinct tile (e.g. the letter F).
+    F,
+}
+
+impl SymmetryClass {
+    /// The members of the dihedral group of order 8 that produce a tile distinct from all the
+    /// others, for a tile with this symmetry class.
+    pub fn orientations(self) -> &'static [Orientation] {
+        use Orientation::*;
+        match self {
+            SymmetryClass::X => &[Original],
+            SymmetryClass::I | SymmetryClass::Diagonal => &[Original, Clockwise90],
+            SymmetryClass::T | SymmetryClass::L => {
+                &[Original, Clockwise90, Clockwise180, Clockwise270]
+            }
+            SymmetryClass::F => &[
+                Original,
+                Clockwise90,
+                Clockwise180,
+                Clockwise270,
+                DiagonalFlip,
+                DiagonalFlippedClockwise90,
+                DiagonalFlippedClockwise180,
+                DiagonalFlippedClockwise270,
+            ],
+        }
+    }
+}
+
+/// An edge code that can be mirrored. Flipping a tile reverses the order in which each edge's
+/// code is encountered, so asymmetric codes (e.g. a label for a path that enters on the left
+/// and exits on the right) still match their mirror image rather than silently mismatching.
+pub trait ReversibleEdgeLabel {
+    fn reversed(&self) -> Self;
+}
+
+impl ReversibleEdgeLabel for String {
+    fn reversed(&self) -> Self {
+        self.chars().rev().collect()
+    }
+}
+
+impl ReversibleEdgeLabel for u32 {
+    fn reversed(&self) -> Self {
+        *self
+    }
+}
+
+fn rotate_ccw(direction: CardinalDirection, quarter_turns: u8) -> CardinalDirection {
+    use CardinalDirection::*;
+    const ORDER: [CardinalDirection; 4] = [North, West, South, East];
+    let index = ORDER.iter().position(|&d| d == direction).unwrap();
+    ORDER[(index + quarter_turns as usize) % 4]
+}
+
+fn flip_horizontal(direction: CardinalDirection) -> CardinalDirection {
+    use CardinalDirection::*;
+    match direction {
+        East => West,
+        West => East,
+        North => North,
+        South => South,
+    }
+}
+
+/// For a tile transformed by `orientation`, the direction in the original (untransformed) tile
+/// whose edge code ends up on side `direction` of the transformed tile.
+fn source_direction(direction: CardinalDirection, orientation: Orientation) -> CardinalDirection {
+    use Orientation::*;
+    match orientation {
+        Original => direction,
+        Clockwise90 => rotate_ccw(direction, 1),
+        Clockwise180 => rotate_ccw(direction, 2),
+        Clockwise270 => rotate_ccw(direction, 3),
+        DiagonalFlip => flip_horizontal(direction),
+        DiagonalFlippedClockwise90 => flip_horizontal(rotate_ccw(direction, 1)),
+        DiagonalFlippedClockwise180 => flip_horizontal(rotate_ccw(direction, 2)),
+        DiagonalFlippedClockwise270 => flip_horizontal(rotate_ccw(direction, 3)),
+    }
+}
+
+fn is_flipped(orientation: Orientation) -> bool {
+    use Orientation::*;
+    matches!(
+        orientation,
+        DiagonalFlip
+            | DiagonalFlippedClockwise90
+            | DiagonalFlippedClockwise180
+            | DiagonalFlippedClockwise270
+    )
+}
+
+/// A tile authored once, alongside the symmetry class that determines how many distinct
+/// oriented copies `expand_tile` should produce from it.
+pub struct TransformedTile<T, E> {
+    pub value: T,
+    pub edges: CardinalDirectionTable<E>,
+    pub weight: Option<NonZeroU32>,
+    pub symmetry: SymmetryClass,
+}
+
+/// Expands a single authored tile into every distinct member of the dihedral group of order 8
+/// implied by its symmetry class, permuting edge codes to match so the result plugs directly
+/// into `SimpleTiledPatterns::new`.
+pub fn expand_tile<T, E>(
+    tile: TransformedTile<T, E>,
+    transform_value: impl Fn(&T, Orientation) -> T,
+) -> Vec<Tile<T, E>>
+where
+    E: Clone + Default + ReversibleEdgeLabel,
+{
+    tile.symmetry
+        .orientations()
+        .iter()
+        .map(|&orientation| {
+            let value = transform_value(&tile.value, orientation);
+            let mut edges = CardinalDirectionTable::default();
+            for direction in CardinalDirections {
+                let edge = tile.edges[source_direction(direction, orientation)].clone();
+                edges[direction] = if is_flipped(orientation) {
+                    edge.reversed()
+                } else {
+                    edge
+                };
+            }
+            Tile::new(value, edges, tile.weight)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn orientation_counts_per_symmetry_class() {
+        assert_eq!(SymmetryClass::X.orientations().len(), 1);
+        assert_eq!(SymmetryClass::I.orientations().len(), 2);
+        assert_eq!(SymmetryClass::Diagonal.orientations().len(), 2);
+        assert_eq!(SymmetryClass::T.orientations().len(), 4);
+        assert_eq!(SymmetryClass::L.orientations().len(), 4);
+        assert_eq!(SymmetryClass::F.orientations().len(), 8);
+    }
+
+    #[test]
+    fn expand_tile_produces_one_tile_per_orientation() {
+        let tile = TransformedTile {
+            value: (),
+            edges: edges("ab", "cd", "ef", "gh"),
+            weight: None,
+            symmetry: SymmetryClass::F,
+        };
+        let expanded = expand_tile(tile, |_, _| ());
+        assert_eq!(expanded.len(), 8);
+    }
+
+    #[test]
+    fn diagonal_flip_reverses_the_mirrored_edges() {
+        // An asymmetric edge code should come out reversed on a flipped tile; north and south
+        // stay on their own side, while east and west swap sides.
+        let tile = TransformedTile {
+            value: (),
+            edges: edges("ab", "cd", "ef", "gh"),
+            weight: None,
+            symmetry: SymmetryClass::F,
+        };
+        let expanded = expand_tile(tile, |_, _| ());
+        // `SymmetryClass::F::orientations()` lists `DiagonalFlip` at index 4.
+        let flipped = expanded[4].edges();
+        assert_eq!(flipped[CardinalDirection::North], "ba");
+        assert_eq!(flipped[CardinalDirection::South], "dc");
+        assert_eq!(flipped[CardinalDirection::East], "hg");
+        assert_eq!(flipped[CardinalDirection::West], "fe");
+    }
+
+    fn edges(
+        north: &str,
+        south: &str,
+        east: &str,
+        west: &str,
+    ) -> CardinalDirectionTable<String> {
+        let mut table = CardinalDirectionTable::default();
+        table[CardinalDirection::North] = north.to_string();
+        table[CardinalDirection::South] = south.to_string();
+        table[CardinalDirection::East] = east.to_string();
+        table[CardinalDirection::West] = west.to_string();
+        table
+    }
+}